@@ -0,0 +1,267 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::model::{
+    greeting_envelope, Address, BirthDate, BirthdayService, Clock, DispatchError, DispatchReport,
+    DispatchResult, Email, Employee, EmployeeRepository, Envelope, EnvelopeDispatcher, FullName,
+    NonEmptyString, SlackService,
+};
+use crate::smtp::{Credentials, SmtpService};
+
+/// Errors raised while loading or validating a [`Config`].
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The TOML file could not be read from disk.
+    Read(String),
+    /// The file was not valid TOML or did not match the schema.
+    Parse(String),
+    /// The parsed config was structurally valid but semantically wrong.
+    Invalid(String),
+}
+
+/// The channel whose transport section the config must provide. Dispatch
+/// itself routes each employee by `Address` through every configured transport.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    Email,
+    Slack,
+}
+
+/// The employee data source. Mirrors the `[mail]` account maps of the
+/// TOML-configured mail clients: a small table that names where records live.
+#[derive(Debug, Deserialize)]
+pub struct SourceConfig {
+    pub path: String,
+}
+
+/// SMTP transport settings for the email channel.
+#[derive(Debug, Deserialize)]
+pub struct EmailConfig {
+    pub host: String,
+    pub port: u16,
+    #[serde(default)]
+    pub tls: bool,
+    pub from: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+/// Webhook settings for the Slack channel.
+#[derive(Debug, Deserialize)]
+pub struct SlackConfig {
+    pub webhook_token: String,
+}
+
+/// Parsed, validated runtime configuration.
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    pub channel: Channel,
+    pub source: SourceConfig,
+    pub email: Option<EmailConfig>,
+    pub slack: Option<SlackConfig>,
+}
+
+impl Config {
+    /// Load and validate a config from a TOML file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Config, ConfigError> {
+        let raw = fs::read_to_string(path).map_err(|e| ConfigError::Read(e.to_string()))?;
+        let config: Config =
+            toml::from_str(&raw).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.source.path.is_empty() {
+            return Err(ConfigError::Invalid("source.path must not be empty".to_owned()));
+        }
+        match self.channel {
+            Channel::Email if self.email.is_none() => Err(ConfigError::Invalid(
+                "channel = \"email\" requires an [email] section".to_owned(),
+            )),
+            Channel::Slack if self.slack.is_none() => Err(ConfigError::Invalid(
+                "channel = \"slack\" requires a [slack] section".to_owned(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Wire up the repository and a [`Router`] holding every transport the
+    /// config enables, so dispatch can route each employee by `Address` at
+    /// runtime rather than through a single hard-coded turbofish type.
+    pub fn build(self) -> Runtime {
+        let repository = Box::new(FileEmployeeRepository {
+            path: self.source.path,
+        });
+        let email = self.email.map(|email| {
+            let mut service =
+                SmtpService::new(email.host, email.port, email.from).with_tls(email.tls);
+            if let (Some(username), Some(password)) = (email.username, email.password) {
+                service = service.with_credentials(Credentials { username, password });
+            }
+            service
+        });
+        let slack = self.slack.map(|_| SlackService());
+        Runtime {
+            repository,
+            router: Router { email, slack },
+        }
+    }
+}
+
+/// Routes each envelope to the transport matching its `Address` variant:
+/// [`Address::Email`] over SMTP, [`Address::Slack`] over the Slack path. A
+/// channel whose section was omitted from the config is absent here, so an
+/// employee on that channel is reported as a failed — not silently dropped —
+/// dispatch.
+pub struct Router {
+    pub email: Option<SmtpService>,
+    pub slack: Option<SlackService>,
+}
+
+impl EnvelopeDispatcher for Router {
+    type Repr<T> = Result<T, DispatchError>;
+
+    fn prepare(&self, employee: &Employee) -> Self::Repr<Envelope> {
+        match &employee.address {
+            Address::Email(_) if self.email.is_none() => Err(DispatchError::GenericError(
+                "no email transport configured".to_owned(),
+            )),
+            Address::Slack(_) if self.slack.is_none() => Err(DispatchError::GenericError(
+                "no slack transport configured".to_owned(),
+            )),
+            _ => Ok(greeting_envelope(employee)),
+        }
+    }
+
+    fn send(&self, msg: Self::Repr<Envelope>) -> Self::Repr<()> {
+        let envelope = msg?;
+        match &envelope.to {
+            Address::Email(_) => {
+                let smtp = self.email.as_ref().expect("checked in prepare");
+                smtp.send(Ok(envelope)).into_outcome()
+            }
+            Address::Slack(_) => {
+                let slack = self.slack.as_ref().expect("checked in prepare");
+                slack.send(Ok(envelope)).into_outcome()
+            }
+        }
+    }
+}
+
+/// The fully wired components ready to feed the dispatch pipeline.
+pub struct Runtime {
+    pub repository: Box<dyn EmployeeRepository>,
+    pub router: Router,
+}
+
+impl Runtime {
+    /// Greet every employee celebrating their birthday on `clock`'s "today",
+    /// routing each one by their `Address` through the [`Router`].
+    pub fn dispatch_birthdays(&self, clock: &dyn Clock) -> Result<DispatchReport, String> {
+        BirthdayService::new(self.repository.as_ref(), clock).send_greetings_all(&self.router)
+    }
+}
+
+/// Reads employees from a line-oriented file of
+/// `first,last,address,birth_date` records, where `address` is either an email
+/// or a `slack:<handle>` token and `birth_date` is `YYYY-MM-DD`.
+pub struct FileEmployeeRepository {
+    path: String,
+}
+
+impl EmployeeRepository for FileEmployeeRepository {
+    fn get_employees(&self) -> Result<Vec<Employee>, String> {
+        let raw = fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        raw.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(parse_record)
+            .collect()
+    }
+}
+
+fn parse_record(line: &str) -> Result<Employee, String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let [first, last, address, birth_date] = fields.as_slice() else {
+        return Err(format!("expected 4 comma-separated fields, got: {line}"));
+    };
+
+    let address = if let Some(handle) = address.strip_prefix("slack:") {
+        Address::Slack(handle.to_owned())
+    } else {
+        Address::Email(
+            Email::new((*address).to_owned()).map_err(|_| format!("invalid email: {address}"))?,
+        )
+    };
+    let birth_date = NaiveDate::parse_from_str(birth_date, "%Y-%m-%d")
+        .map_err(|e| e.to_string())
+        .and_then(|d| BirthDate::new(d).map_err(|e| format!("{e:?}")))?;
+
+    Ok(Employee {
+        name: FullName {
+            first_name: NonEmptyString::new((*first).to_owned()).map_err(|e| format!("{e:?}"))?,
+            last_name: NonEmptyString::new((*last).to_owned()).map_err(|e| format!("{e:?}"))?,
+        },
+        address,
+        birth_date,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    struct FixedClock(NaiveDate);
+    impl Clock for FixedClock {
+        fn today(&self) -> NaiveDate {
+            self.0
+        }
+    }
+
+    fn write(name: &str, contents: &str) -> String {
+        let path = env::temp_dir().join(name);
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn from_file_round_trips_a_slack_config() {
+        let data = write("bg_people_roundtrip.csv", "");
+        let toml = format!(
+            "channel = \"slack\"\n\n[source]\npath = \"{data}\"\n\n[slack]\nwebhook_token = \"xoxb-123\"\n"
+        );
+        let path = write("bg_config_roundtrip.toml", &toml);
+
+        let config = Config::from_file(path).expect("valid config");
+        assert_eq!(config.channel, Channel::Slack);
+        assert_eq!(config.source.path, data);
+        assert_eq!(config.slack.as_ref().unwrap().webhook_token, "xoxb-123");
+    }
+
+    #[test]
+    fn build_then_dispatch_greets_todays_birthdays() {
+        let data = write(
+            "bg_people_dispatch.csv",
+            "Jane,Doe,slack:jane,2014-07-08\nJohn,Roe,slack:john,2014-01-01\n",
+        );
+        let toml = format!(
+            "channel = \"slack\"\n\n[source]\npath = \"{data}\"\n\n[slack]\nwebhook_token = \"xoxb-123\"\n"
+        );
+        let path = write("bg_config_dispatch.toml", &toml);
+
+        let runtime = Config::from_file(path).expect("valid config").build();
+        let clock = FixedClock(NaiveDate::from_ymd_opt(2024, 7, 8).unwrap());
+        let report = runtime
+            .dispatch_birthdays(&clock)
+            .expect("repository is reachable");
+
+        // Only Jane's birthday falls on 2024-07-08.
+        assert_eq!(report.succeeded, 1);
+        assert_eq!(report.failed, 0);
+    }
+}
@@ -0,0 +1,180 @@
+use chrono::Utc;
+
+use crate::model::{Address, Envelope};
+
+/// Maximum length of a full RFC 2047 encoded-word, including the
+/// `=?UTF-8?B?` prefix and `?=` suffix.
+const MAX_ENCODED_WORD: usize = 75;
+/// Column at which header lines are folded (RFC 5322 recommends 78).
+const FOLD_COLUMN: usize = 78;
+
+/// Encode an [`Envelope`] into RFC 5322 wire bytes ready for an SMTP `DATA`
+/// phase. Emits the `To`, `Subject`, `Date` and `MIME-Version` headers plus the
+/// body, RFC 2047 encoding any header value that contains non-ASCII and
+/// declaring `charset=UTF-8` for non-ASCII bodies.
+pub fn encode(envelope: &Envelope) -> Vec<u8> {
+    let recipient = match &envelope.to {
+        Address::Email(email) => email.as_str().to_owned(),
+        Address::Slack(handle) => handle.clone(),
+    };
+
+    let mut out = String::new();
+    out.push_str(&header("To", &recipient));
+    out.push_str(&header("Subject", envelope.message.subject.as_str()));
+    out.push_str(&header("Date", &Utc::now().to_rfc2822()));
+    out.push_str("MIME-Version: 1.0\r\n");
+
+    let body = envelope.message.body.as_str();
+    let encoded_body = if body.is_ascii() {
+        out.push_str("Content-Type: text/plain; charset=US-ASCII\r\n");
+        out.push_str("Content-Transfer-Encoding: 7bit\r\n");
+        body.to_owned()
+    } else {
+        // Use quoted-printable rather than `8bit`, which would require the
+        // `8BITMIME` ESMTP extension the handshake never negotiates.
+        out.push_str("Content-Type: text/plain; charset=UTF-8\r\n");
+        out.push_str("Content-Transfer-Encoding: quoted-printable\r\n");
+        quoted_printable(body)
+    };
+
+    out.push_str("\r\n");
+    out.push_str(&encoded_body);
+    out.into_bytes()
+}
+
+/// Encode a body as RFC 2045 quoted-printable: printable ASCII (except `=`),
+/// tab, CR and LF pass through, everything else becomes `=XX` hex octets.
+fn quoted_printable(body: &str) -> String {
+    let mut out = String::new();
+    for &byte in body.as_bytes() {
+        match byte {
+            b'=' => out.push_str("=3D"),
+            0x20..=0x3C | 0x3E..=0x7E | b'\t' | b'\r' | b'\n' => out.push(byte as char),
+            _ => out.push_str(&format!("={byte:02X}")),
+        }
+    }
+    out
+}
+
+/// Render `Name: value`, RFC 2047 encoding the value when it is non-ASCII and
+/// folding the resulting line at [`FOLD_COLUMN`].
+fn header(name: &str, value: &str) -> String {
+    let rendered = if value.is_ascii() {
+        value.to_owned()
+    } else {
+        encoded_words(value).join(" ")
+    };
+    fold(&format!("{}: {}", name, rendered))
+}
+
+/// Split a non-ASCII value into RFC 2047 base64 encoded-words, each no longer
+/// than [`MAX_ENCODED_WORD`] characters, never splitting a UTF-8 scalar.
+fn encoded_words(value: &str) -> Vec<String> {
+    const PREFIX: &str = "=?UTF-8?B?";
+    const SUFFIX: &str = "?=";
+    // Base64 expands 3 bytes to 4 chars; cap the payload at a multiple of 3
+    // bytes. Each word is self-contained; a word cut short at a character
+    // boundary may end in `=` padding.
+    let budget = MAX_ENCODED_WORD - PREFIX.len() - SUFFIX.len();
+    let max_bytes = (budget / 4) * 3;
+
+    let mut words = Vec::new();
+    let mut chunk: Vec<u8> = Vec::new();
+    for ch in value.chars() {
+        let mut buf = [0u8; 4];
+        let encoded = ch.encode_utf8(&mut buf).as_bytes();
+        if chunk.len() + encoded.len() > max_bytes && !chunk.is_empty() {
+            words.push(format!("{}{}{}", PREFIX, base64(&chunk), SUFFIX));
+            chunk.clear();
+        }
+        chunk.extend_from_slice(encoded);
+    }
+    if !chunk.is_empty() {
+        words.push(format!("{}{}{}", PREFIX, base64(&chunk), SUFFIX));
+    }
+    words
+}
+
+/// Fold a header line at [`FOLD_COLUMN`] using CRLF plus a leading space,
+/// breaking at existing whitespace so individual tokens stay intact.
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut column = 0usize;
+    for (i, token) in line.split(' ').enumerate() {
+        let sep = if i == 0 { 0 } else { 1 };
+        if column + sep + token.len() > FOLD_COLUMN && column > 0 {
+            out.push_str("\r\n ");
+            column = 1;
+            out.push_str(token);
+            column += token.len();
+        } else {
+            if sep == 1 {
+                out.push(' ');
+            }
+            out.push_str(token);
+            column += sep + token.len();
+        }
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// Minimal standard-alphabet base64 encoder (no external dependency).
+pub(crate) fn base64(input: &[u8]) -> String {
+    const TABLE: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | (b[2] as u32);
+        out.push(TABLE[((n >> 18) & 63) as usize] as char);
+        out.push(TABLE[((n >> 12) & 63) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            TABLE[((n >> 6) & 63) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            TABLE[(n & 63) as usize] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_matches_known_vectors() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+    }
+
+    #[test]
+    fn ascii_header_is_left_untouched() {
+        assert_eq!(header("Subject", "Happy birthday"), "Subject: Happy birthday\r\n");
+    }
+
+    #[test]
+    fn non_ascii_header_is_encoded_and_bounded() {
+        let long = "é".repeat(80);
+        let rendered = header("Subject", &long);
+        for word in rendered
+            .replace("\r\n ", " ")
+            .trim_start_matches("Subject: ")
+            .split_whitespace()
+        {
+            assert!(word.len() <= MAX_ENCODED_WORD, "{} too long", word);
+            assert!(word.starts_with("=?UTF-8?B?") && word.ends_with("?="));
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod config;
+pub mod encode;
+pub mod model;
+pub mod smtp;
@@ -2,7 +2,9 @@
 
 use std::{convert::Infallible, ops::FromResidual};
 
-use chrono::{NaiveDate, Utc};
+use chrono::{Datelike, NaiveDate, Utc};
+
+use crate::smtp::SmtpError;
 
 pub struct NonEmptyString {
     inner: String,
@@ -22,6 +24,10 @@ impl NonEmptyString {
             _ => Ok(NonEmptyString { inner: input }),
         }
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
 }
 
 type Name = NonEmptyString;
@@ -39,6 +45,32 @@ pub struct Employee {
 
 pub trait EmployeeRepository {
     fn get_employees(&self) -> Result<Vec<Employee>, String>;
+
+    /// Employees celebrating their birthday on `date`. The default filters over
+    /// [`get_employees`](Self::get_employees); data sources that can index by
+    /// date may override this to push the predicate down to the store.
+    fn birthdays_on(&self, date: NaiveDate) -> Result<Vec<Employee>, String> {
+        Ok(self
+            .get_employees()?
+            .into_iter()
+            .filter(|e| e.birth_date.celebrated_on(date))
+            .collect())
+    }
+}
+
+/// A source of "today", injected so dispatch can be tested at a fixed date
+/// rather than against a bare [`Utc::now`].
+pub trait Clock {
+    fn today(&self) -> NaiveDate;
+}
+
+/// The production clock, reading the current UTC date.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Utc::now().naive_utc().date()
+    }
 }
 
 #[derive(Clone)]
@@ -47,20 +79,72 @@ pub struct Email {
 }
 
 pub enum EmailValidationError {
-    InvalidFormat(String),
+    MissingAt,
+    EmptyLocalPart,
+    InvalidLocalPart,
+    InvalidDomain,
 }
 
 impl Email {
     pub fn new(input: String) -> Result<Email, EmailValidationError> {
-        match input.len() {
-            0 => Err(EmailValidationError::InvalidFormat(
-                "specified email is invalid".to_owned(),
-            )),
-            _ => Ok(Email { inner: input }),
+        let at = input
+            .rfind('@')
+            .ok_or(EmailValidationError::MissingAt)?;
+        let (local, domain) = (&input[..at], &input[at + 1..]);
+
+        if local.is_empty() {
+            return Err(EmailValidationError::EmptyLocalPart);
+        }
+        if !is_valid_local(local) {
+            return Err(EmailValidationError::InvalidLocalPart);
         }
+        if !is_valid_domain(domain) {
+            return Err(EmailValidationError::InvalidDomain);
+        }
+
+        Ok(Email {
+            inner: format!("{}@{}", local, domain.to_ascii_lowercase()),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+
+    /// The part before the `@`, as originally supplied.
+    pub fn local_part(&self) -> &str {
+        let at = self.inner.rfind('@').expect("validated on construction");
+        &self.inner[..at]
+    }
+
+    /// The domain after the `@`, normalized to lowercase.
+    pub fn domain(&self) -> &str {
+        let at = self.inner.rfind('@').expect("validated on construction");
+        &self.inner[at + 1..]
     }
 }
 
+fn is_valid_local(local: &str) -> bool {
+    !has_dot_defect(local) && !local.chars().any(|c| c.is_whitespace())
+}
+
+fn is_valid_domain(domain: &str) -> bool {
+    if domain.is_empty() || has_dot_defect(domain) {
+        return false;
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    // Require at least one dot-separated label boundary (e.g. `example.com`).
+    labels.len() >= 2
+        && labels
+            .iter()
+            .all(|l| !l.is_empty() && !l.chars().any(|c| c.is_whitespace()))
+}
+
+/// Reject leading/trailing dots and consecutive dots.
+fn has_dot_defect(part: &str) -> bool {
+    part.starts_with('.') || part.ends_with('.') || part.contains("..")
+}
+
 #[derive(Debug)]
 pub struct BirthDate {
     inner: NaiveDate,
@@ -81,12 +165,65 @@ impl BirthDate {
             Ok(BirthDate { inner: input })
         }
     }
+
+    /// Whether this birthday is celebrated on `date`. A Feb-29 birthday is
+    /// celebrated on Feb 28 in non-leap years.
+    pub fn celebrated_on(&self, date: NaiveDate) -> bool {
+        let (month, day) = (self.inner.month(), self.inner.day());
+        if month == date.month() && day == date.day() {
+            return true;
+        }
+        month == 2
+            && day == 29
+            && date.month() == 2
+            && date.day() == 28
+            && NaiveDate::from_ymd_opt(date.year(), 2, 29).is_none()
+    }
 }
 
 pub enum DispatchError {
     GenericError(String),
 }
 
+/// Collapses a dispatcher's `Repr<()>` (which varies per backend) into a
+/// uniform outcome, so a batch run can record each recipient's fate without
+/// caring which transport produced it.
+pub trait DispatchResult {
+    fn into_outcome(self) -> Result<(), DispatchError>;
+}
+
+impl DispatchResult for Result<(), String> {
+    fn into_outcome(self) -> Result<(), DispatchError> {
+        self.map_err(DispatchError::GenericError)
+    }
+}
+
+impl DispatchResult for Option<()> {
+    fn into_outcome(self) -> Result<(), DispatchError> {
+        self.ok_or_else(|| DispatchError::GenericError("dispatch failed".to_owned()))
+    }
+}
+
+impl DispatchResult for Result<(), SmtpError> {
+    fn into_outcome(self) -> Result<(), DispatchError> {
+        self.map_err(|e| DispatchError::GenericError(format!("{e:?}")))
+    }
+}
+
+impl DispatchResult for Result<(), DispatchError> {
+    fn into_outcome(self) -> Result<(), DispatchError> {
+        self
+    }
+}
+
+/// Per-run summary returned by [`BirthdayService::send_greetings_all`].
+pub struct DispatchReport {
+    /// One entry per employee: their display identity and the dispatch outcome.
+    pub outcomes: Vec<(String, Result<(), DispatchError>)>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
 #[derive(Clone)]
 pub enum Address {
     Email(Email),
@@ -103,72 +240,104 @@ pub struct Message {
     pub body: NonEmptyString,
 }
 
-trait EnvelopeDispatcher {
+pub trait EnvelopeDispatcher {
     type Repr<T>;
-    fn prepare(employee: &Employee) -> Self::Repr<Envelope>;
-    fn send(msg: Self::Repr<Envelope>) -> Self::Repr<()>;
+    fn prepare(&self, employee: &Employee) -> Self::Repr<Envelope>;
+    fn send(&self, msg: Self::Repr<Envelope>) -> Self::Repr<()>;
 }
 
-pub struct SlackService();
-impl EnvelopeDispatcher for SlackService {
-    type Repr<T> = Result<T, String>;
-
-    fn send(msg: Result<Envelope, String>) -> Result<(), String> {
-        // Do stuffs with slack
-        let _msg = msg?;
-        Ok(())
-    }
-
-    fn prepare(e: &Employee) -> Result<Envelope, String> {
-        let addr = e.address.clone();
-        Ok(Envelope {
-            to: addr,
-            message: Message {
-                subject: NonEmptyString::new("Happy birthday".to_owned()).unwrap(),
-                body: NonEmptyString::new("Happy birthday".to_owned()).unwrap(),
-            },
-        })
+/// Build the standard birthday-greeting envelope for an employee. Shared by the
+/// dispatcher `prepare` implementations so the message text stays in one place.
+pub fn greeting_envelope(employee: &Employee) -> Envelope {
+    Envelope {
+        to: employee.address.clone(),
+        message: Message {
+            subject: NonEmptyString::new("Happy birthday".to_owned()).unwrap(),
+            body: NonEmptyString::new("Happy birthday".to_owned()).unwrap(),
+        },
     }
 }
 
-pub struct EmailService();
-impl EnvelopeDispatcher for EmailService {
-    type Repr<T> = Option<T>;
+pub struct SlackService();
+impl EnvelopeDispatcher for SlackService {
+    type Repr<T> = Result<T, String>;
 
-    fn send(msg: Option<Envelope>) -> Option<()> {
-        // Do stuffs with slack
-        let _msg = msg?;
-        Some(())
+    fn prepare(&self, e: &Employee) -> Result<Envelope, String> {
+        Ok(greeting_envelope(e))
     }
 
-    fn prepare(e: &Employee) -> Option<Envelope> {
-        let addr = e.address.clone();
-        Some(Envelope {
-            to: addr,
-            message: Message {
-                subject: NonEmptyString::new("Happy birthday".to_owned()).unwrap(),
-                body: NonEmptyString::new("Happy birthday".to_owned()).unwrap(),
-            },
-        })
+    fn send(&self, msg: Result<Envelope, String>) -> Result<(), String> {
+        let envelope = msg?;
+        // Format the greeting through the shared encoder. Nothing is posted to
+        // a Slack webhook: every well-formed envelope is reported as sent.
+        let _wire = crate::encode::encode(&envelope);
+        Ok(())
     }
 }
 
 pub struct BirthdayService<'a> {
     employee_repository: Box<&'a dyn EmployeeRepository>,
+    clock: Box<&'a dyn Clock>,
 }
 
 impl<'a> BirthdayService<'a> {
-    fn send_greetings<E, R>(self) -> R
+    pub fn new(
+        employee_repository: &'a dyn EmployeeRepository,
+        clock: &'a dyn Clock,
+    ) -> BirthdayService<'a> {
+        BirthdayService {
+            employee_repository: Box::new(employee_repository),
+            clock: Box::new(clock),
+        }
+    }
+
+    fn send_greetings<E, R>(self, dispatcher: &E) -> R
     where
         E: EnvelopeDispatcher,
         R: FromIterator<E::Repr<()>> + FromResidual<Result<Infallible, String>>,
     {
         self.employee_repository
-            .get_employees()?
+            .birthdays_on(self.clock.today())?
             .iter()
-            .map(|e| E::send(E::prepare(e)))
+            .map(|e| dispatcher.send(dispatcher.prepare(e)))
             .collect::<R>()
     }
+
+    /// Drive every employee independently, collecting a per-recipient outcome
+    /// instead of short-circuiting on the first failure. Only an unreachable
+    /// repository surfaces as a top-level `Err`; an individual dispatch failure
+    /// is captured in its slot so the caller can retry or log it.
+    pub fn send_greetings_all<E>(&self, dispatcher: &E) -> Result<DispatchReport, String>
+    where
+        E: EnvelopeDispatcher,
+        E::Repr<()>: DispatchResult,
+    {
+        let employees = self.employee_repository.birthdays_on(self.clock.today())?;
+        let mut outcomes = Vec::with_capacity(employees.len());
+        let (mut succeeded, mut failed) = (0usize, 0usize);
+        for e in &employees {
+            let outcome = dispatcher.send(dispatcher.prepare(e)).into_outcome();
+            match outcome {
+                Ok(()) => succeeded += 1,
+                Err(_) => failed += 1,
+            }
+            outcomes.push((employee_identity(e), outcome));
+        }
+        Ok(DispatchReport {
+            outcomes,
+            succeeded,
+            failed,
+        })
+    }
+}
+
+/// Human-readable identity used to label an employee in a [`DispatchReport`].
+fn employee_identity(e: &Employee) -> String {
+    format!(
+        "{} {}",
+        e.name.first_name.as_str(),
+        e.name.last_name.as_str()
+    )
 }
 
 #[cfg(test)]
@@ -178,6 +347,7 @@ mod tests {
     use mockall::mock;
 
     use crate::model::*;
+    use crate::smtp::SmtpError;
 
     mock! {
         EmployeeRepository{}
@@ -190,20 +360,140 @@ mod tests {
         SlackService{}
         impl EnvelopeDispatcher for SlackService {
             type Repr<T> = Result<T, String>;
-            fn prepare(e: &Employee) -> <tests::MockSlackService as EnvelopeDispatcher>::Repr<Envelope>;
-            fn send(msg: <tests::MockSlackService as EnvelopeDispatcher>::Repr<Envelope>) -> <tests::MockSlackService as EnvelopeDispatcher>::Repr<()>;
+            fn prepare(&self, e: &Employee) -> <tests::MockSlackService as EnvelopeDispatcher>::Repr<Envelope>;
+            fn send(&self, msg: <tests::MockSlackService as EnvelopeDispatcher>::Repr<Envelope>) -> <tests::MockSlackService as EnvelopeDispatcher>::Repr<()>;
         }
     }
 
+    // Stands in for the SMTP-backed email transport, whose `Repr` carries an
+    // `SmtpError`, so the batch path can be exercised without a live MTA.
     mock! {
         EmailService{}
         impl EnvelopeDispatcher for EmailService {
-            type Repr<T> = Option<T>;
-            fn prepare(e: &Employee) -> <tests::MockEmailService as EnvelopeDispatcher>::Repr<Envelope>;
-            fn send(msg: <tests::MockEmailService as EnvelopeDispatcher>::Repr<Envelope>) -> <tests::MockEmailService as EnvelopeDispatcher>::Repr<()>;
+            type Repr<T> = Result<T, SmtpError>;
+            fn prepare(&self, e: &Employee) -> <tests::MockEmailService as EnvelopeDispatcher>::Repr<Envelope>;
+            fn send(&self, msg: <tests::MockEmailService as EnvelopeDispatcher>::Repr<Envelope>) -> <tests::MockEmailService as EnvelopeDispatcher>::Repr<()>;
+        }
+    }
+
+    #[test]
+    fn email_new_parses_and_normalizes() {
+        let email = Email::new("Jane.Doe@Example.COM".to_owned()).unwrap();
+        assert_eq!(email.local_part(), "Jane.Doe");
+        assert_eq!(email.domain(), "example.com");
+        assert_eq!(email.as_str(), "Jane.Doe@example.com");
+    }
+
+    #[test]
+    fn email_new_rejects_malformed_addresses() {
+        assert!(matches!(
+            Email::new("not-an-email".to_owned()),
+            Err(EmailValidationError::MissingAt)
+        ));
+        assert!(matches!(
+            Email::new("@example.com".to_owned()),
+            Err(EmailValidationError::EmptyLocalPart)
+        ));
+        assert!(matches!(
+            Email::new("jane..doe@example.com".to_owned()),
+            Err(EmailValidationError::InvalidLocalPart)
+        ));
+        assert!(matches!(
+            Email::new("jane@localhost".to_owned()),
+            Err(EmailValidationError::InvalidDomain)
+        ));
+        assert!(matches!(
+            Email::new("jane@example..com".to_owned()),
+            Err(EmailValidationError::InvalidDomain)
+        ));
+    }
+
+    struct FixedClock(NaiveDate);
+    impl Clock for FixedClock {
+        fn today(&self) -> NaiveDate {
+            self.0
+        }
+    }
+
+    fn employee(handle: &str) -> Employee {
+        Employee {
+            address: Address::Slack(handle.into()),
+            birth_date: BirthDate::new(NaiveDate::from_ymd_opt(2014, 7, 8).unwrap()).unwrap(),
+            name: FullName {
+                first_name: Name::new("a".into()).unwrap(),
+                last_name: Name::new("b".into()).unwrap(),
+            },
         }
     }
 
+    #[test]
+    fn feb_29_birthday_is_celebrated_on_feb_28_in_non_leap_years() {
+        let birth = BirthDate::new(NaiveDate::from_ymd_opt(2000, 2, 29).unwrap()).unwrap();
+        // 2023 is not a leap year: celebrate on Feb 28.
+        assert!(birth.celebrated_on(NaiveDate::from_ymd_opt(2023, 2, 28).unwrap()));
+        assert!(!birth.celebrated_on(NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()));
+        // 2024 is a leap year: only Feb 29 counts.
+        assert!(birth.celebrated_on(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()));
+        assert!(!birth.celebrated_on(NaiveDate::from_ymd_opt(2024, 2, 28).unwrap()));
+    }
+
+    #[test]
+    fn send_greetings_all_captures_failures_without_aborting() {
+        let mut employee_repository_mock = MockEmployeeRepository::new();
+        employee_repository_mock
+            .expect_get_employees()
+            .times(1)
+            .returning(|| Ok(vec![employee("pippo"), employee("pluto")]));
+
+        let clock = FixedClock(NaiveDate::from_ymd_opt(2024, 7, 8).unwrap());
+        let birthday_service = BirthdayService::new(&employee_repository_mock, &clock);
+
+        let mut dispatcher = MockSlackService::new();
+        dispatcher
+            .expect_prepare()
+            .times(2)
+            .returning(|e| Ok(greeting_envelope(e)));
+        dispatcher
+            .expect_send()
+            .times(2)
+            .returning(|_| Err("boom".to_owned()));
+
+        let report = birthday_service
+            .send_greetings_all(&dispatcher)
+            .expect("repository is reachable");
+        assert_eq!(report.succeeded, 0);
+        assert_eq!(report.failed, 2);
+        assert_eq!(report.outcomes.len(), 2);
+    }
+
+    #[test]
+    fn send_greetings_all_over_email_transport_captures_smtp_errors() {
+        let mut employee_repository_mock = MockEmployeeRepository::new();
+        employee_repository_mock
+            .expect_get_employees()
+            .times(1)
+            .returning(|| Ok(vec![employee("pippo"), employee("pluto")]));
+
+        let clock = FixedClock(NaiveDate::from_ymd_opt(2024, 7, 8).unwrap());
+        let birthday_service = BirthdayService::new(&employee_repository_mock, &clock);
+
+        let mut dispatcher = MockEmailService::new();
+        dispatcher
+            .expect_prepare()
+            .times(2)
+            .returning(|e| Ok(greeting_envelope(e)));
+        dispatcher
+            .expect_send()
+            .times(2)
+            .returning(|_| Err(SmtpError::UnsupportedAddress));
+
+        let report = birthday_service
+            .send_greetings_all(&dispatcher)
+            .expect("repository is reachable");
+        assert_eq!(report.succeeded, 0);
+        assert_eq!(report.failed, 2);
+    }
+
     #[test]
     fn end_to_end_should_send_greetings_with_dependency_injection() {
         let mut employee_repository_mock = MockEmployeeRepository::new();
@@ -223,12 +513,11 @@ mod tests {
                 }])
             });
 
-        let birthday_service = BirthdayService {
-            employee_repository: Box::new(&employee_repository_mock),
-        };
+        let clock = FixedClock(NaiveDate::from_ymd_opt(2024, 7, 8).unwrap());
+        let birthday_service = BirthdayService::new(&employee_repository_mock, &clock);
 
-        let prepare_ctx = MockSlackService::prepare_context();
-        prepare_ctx.expect().times(1).returning(|_| {
+        let mut slack = MockSlackService::new();
+        slack.expect_prepare().times(1).returning(|_| {
             Ok(Envelope {
                 to: Address::Slack("pippo".into()),
                 message: Message {
@@ -237,35 +526,23 @@ mod tests {
                 },
             })
         });
-
-        let send_ctx = MockSlackService::send_context();
-        send_ctx.expect().times(1).returning(|_| Ok(()));
+        slack.expect_send().times(1).returning(|_| Ok(()));
 
         //
-        // Open for extensions, closed for modification! We define target dispatcher and effect only at call site! Everything else does not change!
+        // Open for extensions, closed for modification! We inject the target
+        // dispatcher instance at the call site; everything else stays the same.
         //
         assert!(birthday_service
-            .send_greetings::<MockSlackService, Result<Vec<()>, String>>() // <----------- MAGIC IS HERE!
+            .send_greetings::<MockSlackService, Result<Vec<()>, String>>(&slack)
             .is_ok());
 
         // --------------------------------------------------
         // OR if we inject a different service, it works!
-        // let prepare_ctx = MockEmailService::prepare_context();
-        // prepare_ctx.expect().times(1).returning(|_| {
-        //     Some(Envelope {
-        //         to: Address::Slack("pippo".into()),
-        //         message: Message {
-        //             subject: NonEmptyString::new("ciao".to_owned()).unwrap(),
-        //             body: NonEmptyString::new("ciao".to_owned()).unwrap(),
-        //         },
-        //     })
-        // });
-
-        // let send_ctx = MockEmailService::send_context();
-        // send_ctx.expect().times(1).returning(|_| Some(()));
-
+        // let mut email = MockEmailService::new();
+        // email.expect_prepare().times(1).returning(|e| Ok(greeting_envelope(e)));
+        // email.expect_send().times(1).returning(|_| Ok(()));
         // assert!(birthday_service
-        //     .send_greetings::<MockEmailService, Option<Vec<()>>>() // <----------- MAGIC IS HERE!
-        //     .is_some())
+        //     .send_greetings::<MockEmailService, Result<Vec<()>, SmtpError>>(&email)
+        //     .is_ok())
     }
 }
@@ -0,0 +1,197 @@
+use std::cell::RefCell;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+
+use crate::model::{Address, Envelope, EnvelopeDispatcher, Employee};
+
+/// Transport/protocol failures raised while talking to an MTA.
+#[derive(Debug)]
+pub enum SmtpError {
+    /// The TCP connection to the MTA could not be established.
+    Connect(String),
+    /// An I/O error occurred while reading from or writing to the socket.
+    Io(String),
+    /// The server replied with an unexpected status code to a command.
+    UnexpectedReply { command: String, reply: String },
+    /// The envelope could not be routed over SMTP (e.g. a Slack address).
+    UnsupportedAddress,
+    /// TLS was requested but this transport does not implement it, so the
+    /// connection is refused rather than silently falling back to cleartext.
+    TlsUnsupported,
+}
+
+/// Credentials presented to the MTA once, when the handle is opened.
+#[derive(Clone)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// A configured, not-yet-connected SMTP transport.
+///
+/// Mirrors the `Service`/`Handle` split used by the mail-smtp client: the
+/// `Service` carries the static configuration (where the MTA lives and how to
+/// authenticate to it) and hands out a long-lived [`SmtpHandle`] that owns the
+/// open connection.
+pub struct SmtpService {
+    host: String,
+    port: u16,
+    tls: bool,
+    credentials: Option<Credentials>,
+    from: String,
+    /// Lazily opened, then reused for every envelope dispatched over this
+    /// transport — the "create the handle once" half of the Service/Handle pair.
+    handle: RefCell<Option<SmtpHandle>>,
+}
+
+impl SmtpService {
+    pub fn new(host: impl Into<String>, port: u16, from: impl Into<String>) -> SmtpService {
+        SmtpService {
+            host: host.into(),
+            port,
+            tls: false,
+            credentials: None,
+            from: from.into(),
+            handle: RefCell::new(None),
+        }
+    }
+
+    pub fn with_tls(mut self, tls: bool) -> SmtpService {
+        self.tls = tls;
+        self
+    }
+
+    pub fn with_credentials(mut self, credentials: Credentials) -> SmtpService {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Open a connection to the MTA and complete the greeting/authentication
+    /// handshake. The resulting handle is created once and reused for every
+    /// envelope dispatched over this transport.
+    pub fn connect(&self) -> Result<SmtpHandle, SmtpError> {
+        // Refuse rather than transmit AUTH credentials in cleartext: this
+        // transport has no STARTTLS/implicit-TLS implementation yet.
+        if self.tls {
+            return Err(SmtpError::TlsUnsupported);
+        }
+        let stream = TcpStream::connect((self.host.as_str(), self.port))
+            .map_err(|e| SmtpError::Connect(e.to_string()))?;
+        let reader = BufReader::new(
+            stream
+                .try_clone()
+                .map_err(|e| SmtpError::Connect(e.to_string()))?,
+        );
+        let mut handle = SmtpHandle {
+            stream,
+            reader,
+            from: self.from.clone(),
+        };
+        handle.expect("greeting", "220")?;
+        handle.command(&format!("EHLO {}", self.host), "250")?;
+        if let Some(creds) = &self.credentials {
+            handle.authenticate(creds)?;
+        }
+        Ok(handle)
+    }
+}
+
+/// A live SMTP connection that borrows per call to dispatch an [`Envelope`].
+pub struct SmtpHandle {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+    from: String,
+}
+
+impl SmtpHandle {
+    /// Dispatch a single envelope over the open connection, issuing
+    /// `MAIL FROM`/`RCPT TO`/`DATA` and streaming the encoded message. The
+    /// handshake reader is reused so buffered server bytes are not discarded.
+    pub fn dispatch(&mut self, envelope: &Envelope) -> Result<(), SmtpError> {
+        let recipient = match &envelope.to {
+            Address::Email(email) => email.as_str().to_owned(),
+            Address::Slack(_) => return Err(SmtpError::UnsupportedAddress),
+        };
+        let from = self.from.clone();
+        self.command(&format!("MAIL FROM:<{from}>"), "250")?;
+        self.command(&format!("RCPT TO:<{recipient}>"), "250")?;
+        self.command("DATA", "354")?;
+        let body = crate::encode::encode(envelope);
+        self.stream
+            .write_all(&body)
+            .map_err(|e| SmtpError::Io(e.to_string()))?;
+        self.command("\r\n.", "250")?;
+        Ok(())
+    }
+
+    fn authenticate(&mut self, creds: &Credentials) -> Result<(), SmtpError> {
+        self.command(
+            &format!("AUTH PLAIN {}", encode_plain(creds)),
+            "235",
+        )?;
+        Ok(())
+    }
+
+    fn command(&mut self, command: &str, expected: &str) -> Result<String, SmtpError> {
+        self.stream
+            .write_all(command.as_bytes())
+            .and_then(|_| self.stream.write_all(b"\r\n"))
+            .map_err(|e| SmtpError::Io(e.to_string()))?;
+        self.expect(command, expected)
+    }
+
+    fn expect(&mut self, command: &str, expected: &str) -> Result<String, SmtpError> {
+        // Read every line of a multi-line reply (`250-...` up to `250 ...`).
+        let mut reply = String::new();
+        loop {
+            let mut line = String::new();
+            let read = self
+                .reader
+                .read_line(&mut line)
+                .map_err(|e| SmtpError::Io(e.to_string()))?;
+            reply.push_str(&line);
+            if read == 0 || line.as_bytes().get(3) != Some(&b'-') {
+                break;
+            }
+        }
+        if reply.starts_with(expected) {
+            Ok(reply)
+        } else {
+            Err(SmtpError::UnexpectedReply {
+                command: command.to_owned(),
+                reply: reply.trim_end().to_owned(),
+            })
+        }
+    }
+}
+
+fn encode_plain(creds: &Credentials) -> String {
+    let raw = format!("\0{}\0{}", creds.username, creds.password);
+    crate::encode::base64(raw.as_bytes())
+}
+
+/// SMTP-backed dispatcher. Selected for [`Address::Email`] recipients; Slack
+/// addresses keep routing through [`crate::model::SlackService`].
+impl EnvelopeDispatcher for SmtpService {
+    type Repr<T> = Result<T, SmtpError>;
+
+    fn prepare(&self, employee: &Employee) -> Self::Repr<Envelope> {
+        match &employee.address {
+            Address::Email(_) => Ok(crate::model::greeting_envelope(employee)),
+            Address::Slack(_) => Err(SmtpError::UnsupportedAddress),
+        }
+    }
+
+    fn send(&self, msg: Self::Repr<Envelope>) -> Self::Repr<()> {
+        let envelope = msg?;
+        // Open the connection on first use and reuse the live handle for every
+        // subsequent envelope.
+        let mut slot = self.handle.borrow_mut();
+        if slot.is_none() {
+            *slot = Some(self.connect()?);
+        }
+        slot.as_mut()
+            .expect("handle populated above")
+            .dispatch(&envelope)
+    }
+}